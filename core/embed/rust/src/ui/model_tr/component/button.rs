@@ -1,14 +1,26 @@
-use crate::ui::{
-    component::{Component, Event, EventCtx},
-    display::{self, Color, Font},
-    event::{ButtonEvent, PhysicalButton},
-    geometry::{Offset, Point, Rect},
+use core::slice;
+
+use crate::{
+    time::Duration,
+    ui::{
+        component::{Component, Event, EventCtx, TimerToken},
+        display::{self, Color, Font},
+        event::{ButtonEvent, PhysicalButton},
+        geometry::{Offset, Point, Rect},
+    },
 };
 
 use super::theme;
 
 pub enum ButtonMsg {
     Clicked,
+    /// Long press button got pressed, `LongPressed` follows if it is held long
+    /// enough.
+    LongPressStarted,
+    LongPressed,
+    /// Button is still being held, emitted periodically when configured via
+    /// `Button::with_hold_heartbeat`.
+    Holding(Duration),
 }
 
 #[derive(Copy, Clone)]
@@ -33,9 +45,17 @@ pub struct Button<T> {
     content: ButtonContent<T>,
     styles: ButtonStyleSheet,
     state: State,
+    long_press: Option<Duration>,
+    long_timer: Option<TimerToken>,
+    hold_heartbeat: Option<Duration>,
+    hold_timer: Option<TimerToken>,
+    hold_elapsed: Duration,
 }
 
 impl<T: AsRef<str>> Button<T> {
+    /// Horizontal gap between the icons of `ButtonContent::Icons`.
+    pub const ICON_SPACING: i32 = 2;
+
     pub fn new(pos: ButtonPos, content: ButtonContent<T>, styles: ButtonStyleSheet) -> Self {
         Self {
             pos,
@@ -44,6 +64,11 @@ impl<T: AsRef<str>> Button<T> {
             baseline: Point::zero(),
             area: Rect::zero(),
             state: State::Released,
+            long_press: None,
+            long_timer: None,
+            hold_heartbeat: None,
+            hold_timer: None,
+            hold_elapsed: Duration::ZERO,
         }
     }
 
@@ -55,11 +80,56 @@ impl<T: AsRef<str>> Button<T> {
         Self::new(pos, ButtonContent::Icon(image), styles)
     }
 
+    /// Several icons laid out horizontally and centered as a group.
+    pub fn with_icons(
+        pos: ButtonPos,
+        images: &'static [&'static [u8]],
+        styles: ButtonStyleSheet,
+    ) -> Self {
+        Self::new(pos, ButtonContent::Icons(images), styles)
+    }
+
+    /// Emit `ButtonMsg::LongPressed` once the button has been held for
+    /// `duration`. Releasing the button before that still results in a click.
+    pub fn with_long_press(mut self, duration: Duration) -> Self {
+        self.long_press = Some(duration);
+        self
+    }
+
+    pub fn set_long_press(&mut self, duration: Option<Duration>) {
+        self.long_press = duration;
+    }
+
+    pub fn is_longpress(&self) -> bool {
+        self.long_press.is_some()
+    }
+
+    /// Whether a long press is currently in progress, i.e. the button is held
+    /// but the long press threshold has not been reached yet.
+    pub fn is_holding(&self) -> bool {
+        self.long_timer.is_some() && matches!(self.state, State::Pressed)
+    }
+
+    /// Emit `ButtonMsg::Holding` every `interval` while the button is pressed.
+    pub fn with_hold_heartbeat(mut self, interval: Duration) -> Self {
+        self.hold_heartbeat = Some(interval);
+        self
+    }
+
     pub fn content(&self) -> &ButtonContent<T> {
         &self.content
     }
 
-    fn style(&self) -> &ButtonStyle {
+    /// Change the button styles. As the font, and therefore the button width,
+    /// might change, the button is placed again before the next paint.
+    pub fn set_style(&mut self, ctx: &mut EventCtx, styles: ButtonStyleSheet) {
+        self.styles = styles;
+        ctx.request_place();
+        ctx.request_paint();
+    }
+
+    /// Style used for the current button state.
+    pub fn current_style(&self) -> &ButtonStyle {
         match self.state {
             State::Released => self.styles.normal,
             State::Pressed => self.styles.active,
@@ -73,6 +143,36 @@ impl<T: AsRef<str>> Button<T> {
         }
     }
 
+    /// Combined size of `icons` placed next to each other.
+    fn icons_size(icons: &[&'static [u8]]) -> Offset {
+        let mut size = Offset::zero();
+        for (i, icon) in icons.iter().enumerate() {
+            if let Some((icon_size, _)) = display::toif_info(icon) {
+                let spacing = if i > 0 { Self::ICON_SPACING } else { 0 };
+                size = Offset::new(size.x + spacing + icon_size.x, size.y.max(icon_size.y));
+            }
+        }
+        size
+    }
+
+    /// Top-left corner of a group of `size` centered in the button area.
+    fn icons_top_left(&self, size: Offset) -> Point {
+        self.area.center() - Offset::new(size.x / 2, size.y / 2)
+    }
+
+    fn paint_icons(&self, icons: &[&'static [u8]], fg_color: Color, bg_color: Color) {
+        let group = Self::icons_size(icons);
+        let mut top_left = self.icons_top_left(group);
+        for icon in icons {
+            if let Some((size, _)) = display::toif_info(icon) {
+                // Align icons of different heights on their vertical centers.
+                let icon_top_left = top_left + Offset::y((group.y - size.y) / 2);
+                display::icon_top_left(icon_top_left, icon, fg_color, bg_color);
+                top_left = top_left + Offset::x(size.x + Self::ICON_SPACING);
+            }
+        }
+    }
+
     fn placement(
         area: Rect,
         pos: ButtonPos,
@@ -82,15 +182,23 @@ impl<T: AsRef<str>> Button<T> {
         let border_width = if styles.normal.border_horiz { 2 } else { 0 };
         let content_width = match content {
             ButtonContent::Text(text) => styles.normal.font.text_width(text.as_ref()) - 1,
-            ButtonContent::Icon(_icon) => todo!(),
+            ButtonContent::Icon(icon) => Self::icons_size(slice::from_ref(icon)).x,
+            ButtonContent::Icons(icons) => Self::icons_size(icons).x,
         };
-        let button_width = content_width + 2 * border_width;
+        let button_width = (content_width + 2 * border_width).max(theme::MIN_BUTTON_WIDTH);
         let area = match pos {
             ButtonPos::Left => area.split_left(button_width).0,
             ButtonPos::Right => area.split_right(button_width).1,
         };
 
-        let start_of_baseline = area.bottom_left() + Offset::new(border_width, -2);
+        // Keep the text centered in case the button got widened.
+        let text_offset = (button_width - content_width) / 2;
+        // Keep the glyphs inside the button even if the area is too short for the
+        // usual 2px padding below the baseline.
+        let baseline_y = (area.y1 - 2)
+            .max(area.y0 + styles.normal.font.text_height())
+            .min(area.y1);
+        let start_of_baseline = Point::new(area.x0 + text_offset, baseline_y);
 
         (area, start_of_baseline)
     }
@@ -113,30 +221,56 @@ where
         match event {
             Event::Button(ButtonEvent::ButtonPressed(which)) if self.pos.hit(&which) => {
                 self.set(ctx, State::Pressed);
+                if let Some(interval) = self.hold_heartbeat {
+                    self.hold_elapsed = Duration::ZERO;
+                    self.hold_timer = Some(ctx.request_timer(interval));
+                }
+                if let Some(duration) = self.long_press {
+                    self.long_timer = Some(ctx.request_timer(duration));
+                    return Some(ButtonMsg::LongPressStarted);
+                }
             }
             Event::Button(ButtonEvent::ButtonReleased(which)) if self.pos.hit(&which) => {
+                self.long_timer = None;
+                self.hold_timer = None;
                 if matches!(self.state, State::Pressed) {
                     self.set(ctx, State::Released);
                     return Some(ButtonMsg::Clicked);
                 }
             }
+            Event::Timer(token) if self.long_timer == Some(token) => {
+                self.long_timer = None;
+                if matches!(self.state, State::Pressed) {
+                    // Releasing the button afterwards does not result in a click.
+                    self.set(ctx, State::Released);
+                    return Some(ButtonMsg::LongPressed);
+                }
+            }
+            Event::Timer(token) if self.hold_timer == Some(token) => {
+                self.hold_timer = None;
+                if let (State::Pressed, Some(interval)) = (&self.state, self.hold_heartbeat) {
+                    self.hold_elapsed = self.hold_elapsed.checked_add(interval)?;
+                    self.hold_timer = Some(ctx.request_timer(interval));
+                    return Some(ButtonMsg::Holding(self.hold_elapsed));
+                }
+            }
             _ => {}
         };
         None
     }
 
     fn paint(&mut self) {
-        let style = self.style();
+        let style = self.current_style();
+
+        let background_color = style.text_color.negate();
+        if style.border_horiz {
+            display::rect_fill_rounded1(self.area, background_color, theme::BG);
+        } else {
+            display::rect_fill(self.area, background_color)
+        }
 
         match &self.content {
             ButtonContent::Text(text) => {
-                let background_color = style.text_color.negate();
-                if style.border_horiz {
-                    display::rect_fill_rounded1(self.area, background_color, theme::BG);
-                } else {
-                    display::rect_fill(self.area, background_color)
-                }
-
                 display::text(
                     self.baseline,
                     text.as_ref(),
@@ -145,8 +279,11 @@ where
                     background_color,
                 );
             }
-            ButtonContent::Icon(_image) => {
-                todo!();
+            ButtonContent::Icon(icon) => {
+                self.paint_icons(slice::from_ref(icon), style.text_color, background_color)
+            }
+            ButtonContent::Icons(icons) => {
+                self.paint_icons(icons, style.text_color, background_color)
             }
         }
     }
@@ -162,6 +299,7 @@ where
         match &self.content {
             ButtonContent::Text(text) => t.field("text", text),
             ButtonContent::Icon(_) => t.symbol("icon"),
+            ButtonContent::Icons(_) => t.symbol("icons"),
         }
         t.close();
     }
@@ -176,6 +314,7 @@ enum State {
 pub enum ButtonContent<T> {
     Text(T),
     Icon(&'static [u8]),
+    Icons(&'static [&'static [u8]]),
 }
 
 pub struct ButtonStyleSheet {
@@ -188,3 +327,209 @@ pub struct ButtonStyle {
     pub text_color: Color,
     pub border_horiz: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::model_tr::constant;
+
+    use super::*;
+
+    const ICON_UP: &[u8] = include_res!("model_tt/res/scroll-up.toif");
+    const ICON_DOWN: &[u8] = include_res!("model_tt/res/scroll-down.toif");
+
+    fn press(button: &mut Button<&'static str>, ctx: &mut EventCtx) -> Option<ButtonMsg> {
+        button.event(
+            ctx,
+            Event::Button(ButtonEvent::ButtonPressed(PhysicalButton::Left)),
+        )
+    }
+
+    fn release(button: &mut Button<&'static str>, ctx: &mut EventCtx) -> Option<ButtonMsg> {
+        button.event(
+            ctx,
+            Event::Button(ButtonEvent::ButtonReleased(PhysicalButton::Left)),
+        )
+    }
+
+    fn long_press_button() -> Button<&'static str> {
+        Button::with_text(ButtonPos::Left, "HOLD", theme::button_default())
+            .with_long_press(Duration::from_millis(1000))
+    }
+
+    #[test]
+    fn long_press_builder() {
+        let button = Button::with_text(ButtonPos::Left, "OK", theme::button_default());
+        assert!(!button.is_longpress());
+        assert!(long_press_button().is_longpress());
+    }
+
+    #[test]
+    fn long_press_fires_on_timer() {
+        let mut ctx = EventCtx::new();
+        let mut button = long_press_button();
+        assert!(matches!(
+            press(&mut button, &mut ctx),
+            Some(ButtonMsg::LongPressStarted)
+        ));
+        let (token, duration) = ctx.pop_timer().unwrap();
+        assert_eq!(duration, Duration::from_millis(1000));
+        assert!(matches!(
+            button.event(&mut ctx, Event::Timer(token)),
+            Some(ButtonMsg::LongPressed)
+        ));
+        assert!(release(&mut button, &mut ctx).is_none());
+    }
+
+    #[test]
+    fn regular_press_does_not_start_long_press() {
+        let mut ctx = EventCtx::new();
+        let mut button = Button::with_text(ButtonPos::Left, "OK", theme::button_default());
+        assert!(press(&mut button, &mut ctx).is_none());
+        assert!(ctx.pop_timer().is_none());
+    }
+
+    #[test]
+    fn is_holding_tracks_long_press_progress() {
+        let mut ctx = EventCtx::new();
+        let mut button = long_press_button();
+        assert!(!button.is_holding());
+
+        // Released before the threshold.
+        press(&mut button, &mut ctx);
+        assert!(button.is_holding());
+        release(&mut button, &mut ctx);
+        assert!(!button.is_holding());
+
+        // Held until the threshold.
+        press(&mut button, &mut ctx);
+        assert!(button.is_holding());
+        let (token, _) = ctx.pop_timer().unwrap();
+        button.event(&mut ctx, Event::Timer(token));
+        assert!(!button.is_holding());
+        release(&mut button, &mut ctx);
+        assert!(!button.is_holding());
+    }
+
+    #[test]
+    fn hold_heartbeat_reports_elapsed_time() {
+        let mut ctx = EventCtx::new();
+        let mut button = Button::with_text(ButtonPos::Left, "HOLD", theme::button_default())
+            .with_hold_heartbeat(Duration::from_millis(100));
+        press(&mut button, &mut ctx);
+        for expected in [100, 200, 300] {
+            let (token, _) = ctx.pop_timer().unwrap();
+            match button.event(&mut ctx, Event::Timer(token)) {
+                Some(ButtonMsg::Holding(elapsed)) => {
+                    assert_eq!(elapsed, Duration::from_millis(expected))
+                }
+                _ => panic!("expected a heartbeat"),
+            }
+        }
+        assert!(matches!(
+            release(&mut button, &mut ctx),
+            Some(ButtonMsg::Clicked)
+        ));
+        let (token, _) = ctx.pop_timer().unwrap();
+        assert!(button.event(&mut ctx, Event::Timer(token)).is_none());
+    }
+
+    #[test]
+    fn icons_are_centered_as_a_group() {
+        let mut button = Button::<&str>::with_icons(
+            ButtonPos::Left,
+            &[ICON_UP, ICON_DOWN],
+            theme::button_default(),
+        );
+        let area = button.place(constant::screen());
+
+        let (up, _) = display::toif_info(ICON_UP).unwrap();
+        let (down, _) = display::toif_info(ICON_DOWN).unwrap();
+        let group_width = up.x + Button::<&str>::ICON_SPACING + down.x;
+        // Default style has a horizontal border on both sides.
+        assert_eq!(area.width(), group_width + 2 * 2);
+
+        let group = Button::<&str>::icons_size(&[ICON_UP, ICON_DOWN]);
+        assert_eq!(group.x, group_width);
+        let top_left = button.icons_top_left(group);
+        let left_gap = top_left.x - area.x0;
+        let right_gap = area.x1 - (top_left.x + group.x);
+        assert!((left_gap - right_gap).abs() <= 1);
+    }
+
+    #[test]
+    fn short_button_has_minimum_width() {
+        let mut button = Button::with_text(ButtonPos::Right, "X", theme::button_default());
+        let area = button.place(constant::screen());
+        assert!(area.width() >= theme::MIN_BUTTON_WIDTH);
+        assert_eq!(area.x1, constant::screen().x1);
+    }
+
+    #[test]
+    fn stray_release_is_ignored() {
+        let mut ctx = EventCtx::new();
+        let mut button = Button::with_text(ButtonPos::Left, "OK", theme::button_default());
+        assert!(release(&mut button, &mut ctx).is_none());
+        assert!(button.state == State::Released);
+
+        // A regular press and release still results in a click afterwards.
+        press(&mut button, &mut ctx);
+        assert!(matches!(
+            release(&mut button, &mut ctx),
+            Some(ButtonMsg::Clicked)
+        ));
+        assert!(button.state == State::Released);
+    }
+
+    #[test]
+    fn baseline_stays_within_short_area() {
+        let font = theme::button_default().normal.font;
+        let mut button = Button::with_text(ButtonPos::Left, "OK", theme::button_default());
+
+        let area = button.place(constant::screen().split_bottom(font.text_height()).1);
+        assert!(button.baseline.y <= area.y1);
+        assert!(button.baseline.y - font.text_height() >= area.y0);
+
+        let area = button.place(constant::screen().split_bottom(4).1);
+        assert!(button.baseline.y >= area.y0);
+        assert!(button.baseline.y <= area.y1);
+    }
+
+    #[test]
+    fn set_style_updates_width() {
+        let mono = || ButtonStyleSheet {
+            normal: &ButtonStyle {
+                font: theme::FONT_MONO,
+                text_color: theme::BG,
+                border_horiz: false,
+            },
+            active: &ButtonStyle {
+                font: theme::FONT_MONO,
+                text_color: theme::FG,
+                border_horiz: false,
+            },
+        };
+        let mut ctx = EventCtx::new();
+        let mut button = Button::with_text(ButtonPos::Left, "CONFIRM", theme::button_cancel());
+        let area = button.place(constant::screen());
+        assert_eq!(area.width(), theme::FONT_BOLD.text_width("CONFIRM") - 1);
+
+        ctx.clear();
+        button.set_style(&mut ctx, mono());
+        assert!(ctx.needs_place_before_next_event_or_paint());
+        let area = button.place(constant::screen());
+        assert_eq!(area.width(), theme::FONT_MONO.text_width("CONFIRM") - 1);
+    }
+
+    #[test]
+    fn current_style_follows_state() {
+        let mut ctx = EventCtx::new();
+        let styles = theme::button_default();
+        let (normal, active) = (styles.normal, styles.active);
+        let mut button = Button::with_text(ButtonPos::Left, "OK", styles);
+        assert!(core::ptr::eq(button.current_style(), normal));
+        press(&mut button, &mut ctx);
+        assert!(core::ptr::eq(button.current_style(), active));
+        release(&mut button, &mut ctx);
+        assert!(core::ptr::eq(button.current_style(), normal));
+    }
+}
@@ -2,12 +2,13 @@ use super::theme;
 use crate::ui::{
     component::{Child, Component, Event, EventCtx},
     display,
-    geometry::{Insets, Offset, Rect},
+    geometry::{Insets, Offset, Point, Rect},
 };
 
 pub struct Frame<T, U> {
     area: Rect,
     title: U,
+    title_icon: Option<&'static [u8]>,
     content: Child<T>,
 }
 
@@ -16,17 +17,43 @@ where
     T: Component,
     U: AsRef<str>,
 {
+    /// Space between the title icon and the title text.
+    const TITLE_ICON_SPACE: i32 = 2;
+
     pub fn new(title: U, content: T) -> Self {
         Self {
             title,
+            title_icon: None,
             area: Rect::zero(),
             content: Child::new(content),
         }
     }
 
+    /// Paint a small icon in front of the title text.
+    pub fn with_title_icon(mut self, icon: &'static [u8]) -> Self {
+        self.title_icon = Some(icon);
+        self
+    }
+
     pub fn inner(&self) -> &T {
         self.content.inner()
     }
+
+    /// Top-left corner of the title icon, vertically centered in the title
+    /// area, and its size.
+    fn title_icon_placement(&self) -> Option<(Point, Offset)> {
+        let (size, _) = display::toif_info(self.title_icon?)?;
+        let top_left = Point::new(self.area.x0, self.area.center().y - size.y / 2);
+        Some((top_left, size))
+    }
+
+    fn title_baseline(&self) -> Point {
+        let baseline = self.area.bottom_left() - Offset::y(2);
+        match self.title_icon_placement() {
+            Some((_, size)) => baseline + Offset::x(size.x + Self::TITLE_ICON_SPACE),
+            None => baseline,
+        }
+    }
 }
 
 impl<T, U> Component for Frame<T, U>
@@ -52,8 +79,11 @@ where
     }
 
     fn paint(&mut self) {
+        if let (Some(icon), Some((top_left, _))) = (self.title_icon, self.title_icon_placement()) {
+            display::icon_top_left(top_left, icon, theme::FG, theme::BG);
+        }
         display::text(
-            self.area.bottom_left() - Offset::y(2),
+            self.title_baseline(),
             self.title.as_ref(),
             theme::FONT_BOLD,
             theme::FG,
@@ -77,3 +107,31 @@ where
         t.close();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::{component::Empty, model_tr::constant};
+
+    use super::*;
+
+    const ICON: &[u8] = include_res!("model_tt/res/confirm.toif");
+
+    #[test]
+    fn title_icon_is_left_of_title() {
+        let mut frame = Frame::new("Bitcoin", Empty);
+        frame.place(constant::screen());
+        assert!(frame.title_icon_placement().is_none());
+        let plain_baseline = frame.title_baseline();
+
+        let mut frame = Frame::new("Bitcoin", Empty).with_title_icon(ICON);
+        frame.place(constant::screen());
+        let (icon_top_left, icon_size) = frame.title_icon_placement().unwrap();
+        let baseline = frame.title_baseline();
+        assert_eq!(icon_top_left.x, frame.area.x0);
+        assert_eq!(
+            baseline.x,
+            icon_top_left.x + icon_size.x + Frame::<Empty, &str>::TITLE_ICON_SPACE
+        );
+        assert_eq!(baseline.y, plain_baseline.y);
+    }
+}
@@ -18,6 +18,9 @@ pub const GREY_LIGHT: Color = WHITE; // Word/page break characters.
 pub const FG: Color = WHITE; // Default foreground (text & icon) color.
 pub const BG: Color = BLACK; // Default background color.
 
+// Minimum width of a button, so that short labels do not end up as tiny buttons.
+pub const MIN_BUTTON_WIDTH: i32 = 20;
+
 pub fn button_default() -> ButtonStyleSheet {
     ButtonStyleSheet {
         normal: &ButtonStyle {